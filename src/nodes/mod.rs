@@ -0,0 +1,5 @@
+//! A puppet's scene graph: individual [`node::Node`]s stored in a
+//! [`node_tree::NodeTree`].
+
+pub mod node;
+pub mod node_tree;
@@ -0,0 +1,49 @@
+//! A single node in a puppet's node tree.
+
+use glam::Vec2;
+
+/// Stable identifier for a [`Node`] within a
+/// [`NodeTree`](crate::nodes::node_tree::NodeTree).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NodeUuid(pub(crate) u32);
+
+impl From<u32> for NodeUuid {
+    fn from(id: u32) -> Self {
+        NodeUuid(id)
+    }
+}
+
+impl From<NodeUuid> for u32 {
+    fn from(id: NodeUuid) -> Self {
+        id.0
+    }
+}
+
+/// A node in the puppet's scene graph: its rest pose and mesh, plus the
+/// accumulated offsets that `Param` bindings and physics write into
+/// each frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Node {
+    pub uuid: NodeUuid,
+    /// Rest-pose position, before any `Param`/physics offsets.
+    pub position: Vec2,
+
+    pub offset_tx: f32,
+    pub offset_ty: f32,
+    pub offset_sx: f32,
+    pub offset_sy: f32,
+    pub offset_rx: f32,
+    pub offset_ry: f32,
+    pub offset_rz: f32,
+    pub zsort_offset: f32,
+    /// Per-vertex offsets accumulated from `Deform` bindings.
+    pub deform_offset: Vec<Vec2>,
+
+    /// Whether this node is a drawable mesh (as opposed to a plain
+    /// transform/group node).
+    pub is_drawable: bool,
+    pub vertices: Vec<Vec2>,
+    pub uvs: Vec<Vec2>,
+    pub indices: Vec<u32>,
+    pub texture_id: Option<u32>,
+}
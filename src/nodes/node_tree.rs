@@ -0,0 +1,65 @@
+//! Flat storage for a puppet's [`Node`]s.
+
+use glam::Vec2;
+
+use crate::nodes::node::{Node, NodeUuid};
+
+/// A puppet's scene graph, stored as a flat list indexed by
+/// [`NodeUuid`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeTree {
+    pub(crate) nodes: Vec<Node>,
+}
+
+impl NodeTree {
+    /// Returns the node identified by `uuid`, if any.
+    pub fn get(&self, uuid: NodeUuid) -> Option<&Node> {
+        self.nodes.get(u32::from(uuid) as usize)
+    }
+
+    /// Returns a mutable reference to the node identified by `uuid`, if
+    /// any.
+    pub fn get_mut(&mut self, uuid: NodeUuid) -> Option<&mut Node> {
+        self.nodes.get_mut(u32::from(uuid) as usize)
+    }
+
+    /// The node's current world position: its rest position plus its
+    /// accumulated translation offset.
+    pub fn world_position(&self, uuid: NodeUuid) -> Option<Vec2> {
+        self.get(uuid)
+            .map(|node| node.position + Vec2::new(node.offset_tx, node.offset_ty))
+    }
+
+    /// Iterates over every drawable node in the tree.
+    pub fn drawables(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter(|node| node.is_drawable)
+    }
+
+    /// The number of drawable nodes in the tree.
+    pub fn drawable_count(&self) -> usize {
+        self.drawables().count()
+    }
+
+    /// Resets every node's `Param`/physics offsets back to baseline.
+    ///
+    /// `Binding::apply` accumulates onto a node's offsets (so multiple
+    /// bindings targeting the same node within one evaluation pass add
+    /// together), so callers must reset the tree before each full pass
+    /// or offsets will keep growing across frames instead of tracking
+    /// the current parameter values.
+    pub fn reset_offsets(&mut self) {
+        for node in &mut self.nodes {
+            node.offset_tx = 0.0;
+            node.offset_ty = 0.0;
+            node.offset_sx = 0.0;
+            node.offset_sy = 0.0;
+            node.offset_rx = 0.0;
+            node.offset_ry = 0.0;
+            node.offset_rz = 0.0;
+            node.zsort_offset = 0.0;
+            for slot in &mut node.deform_offset {
+                *slot = Vec2::ZERO;
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! `SimplePhysics` pendulum/spring subsystem, driven by
+//! [`PuppetPhysics`](crate::puppet::PuppetPhysics) and consumed by
+//! [`Puppet::update_physics`](crate::puppet::Puppet::update_physics).
+
+#![allow(dead_code)]
+
+use glam::Vec2;
+
+use crate::nodes::node::NodeUuid;
+
+/// Fixed timestep used by the RK4 accumulator. Physics is substepped at
+/// this rate regardless of the caller's frame `dt`, so the simulation
+/// stays stable and deterministic under varying frame times.
+const FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// Which physical model a [`SimplePhysics`] node simulates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimplePhysicsModel {
+    /// A plain driven pendulum.
+    Pendulum,
+    /// A pendulum with an additional restoring spring term toward
+    /// `rest_angle`, scaled by the given spring constant.
+    SpringPendulum { spring_k: f32 },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct PendulumState {
+    angle: f32,
+    angular_vel: f32,
+}
+
+/// An Inochi2D-style `SimplePhysics` node: a point mass on a rigid arm,
+/// anchored to a node whose world position moves each frame, driving a
+/// parameter on the puppet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimplePhysics {
+    /// Node the pendulum is anchored to; its world position each frame
+    /// is the moving pivot the arm swings from.
+    pub anchor: NodeUuid,
+    /// Index into `Puppet::parameters` of the parameter this pendulum
+    /// drives.
+    pub param: usize,
+    /// Length of the rigid arm, in the puppet's pixel space.
+    pub length: f32,
+    /// Angular damping applied to the angular velocity each step.
+    pub damping: f32,
+    /// Rest angle, in radians from straight down, that `reset` returns
+    /// to, that `output_value` measures from, and that a
+    /// `SpringPendulum`'s spring pulls toward. Gravity alone pulls the
+    /// bob toward straight down, not toward this angle.
+    pub rest_angle: f32,
+    /// Which physical model to simulate.
+    pub model: SimplePhysicsModel,
+    /// Divides the bob's offset before it's mapped onto the driven
+    /// parameter's `[min, max]` range.
+    pub output_scale: Vec2,
+
+    state: PendulumState,
+    last_anchor_pos: Option<Vec2>,
+    last_anchor_vel: Vec2,
+    accumulator: f32,
+}
+
+impl SimplePhysics {
+    /// Creates a plain pendulum at rest, anchored to `anchor` and
+    /// driving `Puppet::parameters[param]`.
+    pub fn new(anchor: NodeUuid, param: usize, length: f32, output_scale: Vec2) -> Self {
+        Self {
+            anchor,
+            param,
+            length,
+            damping: 0.5,
+            rest_angle: 0.0,
+            model: SimplePhysicsModel::Pendulum,
+            output_scale,
+            state: PendulumState::default(),
+            last_anchor_pos: None,
+            last_anchor_vel: Vec2::ZERO,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Resets the simulation to rest, discarding velocity, the tracked
+    /// anchor motion, and any accumulated substep time.
+    pub fn reset(&mut self) {
+        self.state = PendulumState {
+            angle: self.rest_angle,
+            angular_vel: 0.0,
+        };
+        self.last_anchor_pos = None;
+        self.last_anchor_vel = Vec2::ZERO;
+        self.accumulator = 0.0;
+    }
+
+    /// Advances the simulation by `dt` seconds given the anchor's
+    /// current world position, substepping with a fixed-timestep RK4
+    /// integrator for stability.
+    pub fn step(&mut self, dt: f32, anchor_pos: Vec2, gravity_accel: f32) {
+        let anchor_vel = match self.last_anchor_pos {
+            Some(prev) if dt > 0.0 => (anchor_pos - prev) / dt,
+            _ => Vec2::ZERO,
+        };
+        let anchor_accel = if dt > 0.0 {
+            (anchor_vel - self.last_anchor_vel) / dt
+        } else {
+            Vec2::ZERO
+        };
+        self.last_anchor_pos = Some(anchor_pos);
+        self.last_anchor_vel = anchor_vel;
+
+        self.accumulator += dt;
+        while self.accumulator >= FIXED_TIMESTEP {
+            self.state = rk4_step(self.state, FIXED_TIMESTEP, |s| {
+                self.angular_accel(s, gravity_accel, anchor_accel)
+            });
+            self.accumulator -= FIXED_TIMESTEP;
+        }
+    }
+
+    /// The bob's current offset from rest, normalized by `output_scale`
+    /// so it can be mapped onto a driven parameter's `[min, max]` range.
+    pub fn output_value(&self) -> Vec2 {
+        let rest = Vec2::new(self.rest_angle.sin(), 1.0 - self.rest_angle.cos());
+        let bob = Vec2::new(self.state.angle.sin(), 1.0 - self.state.angle.cos());
+        (bob - rest) * self.length / self.output_scale
+    }
+
+    fn angular_accel(&self, state: PendulumState, gravity_accel: f32, anchor_accel: Vec2) -> f32 {
+        // Tangential direction of motion at the bob, used to project the
+        // anchor's acceleration into a pseudo-force on the pendulum.
+        let tangent = Vec2::new(state.angle.cos(), state.angle.sin());
+        let anchor_accel_tangential = anchor_accel.dot(tangent);
+
+        let mut accel = -(gravity_accel / self.length) * state.angle.sin()
+            - self.damping * state.angular_vel
+            - anchor_accel_tangential / self.length;
+
+        if let SimplePhysicsModel::SpringPendulum { spring_k } = self.model {
+            accel -= spring_k * (state.angle - self.rest_angle);
+        }
+
+        accel
+    }
+}
+
+/// A single RK4 step of the pendulum's `(angle, angular_vel)` state,
+/// given a closure computing angular acceleration from a state.
+fn rk4_step(state: PendulumState, dt: f32, accel: impl Fn(PendulumState) -> f32) -> PendulumState {
+    let deriv = |s: PendulumState| PendulumState {
+        angle: s.angular_vel,
+        angular_vel: accel(s),
+    };
+    let add = |a: PendulumState, b: PendulumState, scale: f32| PendulumState {
+        angle: a.angle + b.angle * scale,
+        angular_vel: a.angular_vel + b.angular_vel * scale,
+    };
+
+    let k1 = deriv(state);
+    let k2 = deriv(add(state, k1, dt * 0.5));
+    let k3 = deriv(add(state, k2, dt * 0.5));
+    let k4 = deriv(add(state, k3, dt));
+
+    PendulumState {
+        angle: state.angle
+            + (dt / 6.0) * (k1.angle + 2.0 * k2.angle + 2.0 * k3.angle + k4.angle),
+        angular_vel: state.angular_vel
+            + (dt / 6.0)
+                * (k1.angular_vel + 2.0 * k2.angular_vel + 2.0 * k3.angular_vel + k4.angular_vel),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rk4_step_with_zero_accel_is_uniform_motion() {
+        let state = PendulumState {
+            angle: 0.0,
+            angular_vel: 2.0,
+        };
+        let next = rk4_step(state, 0.5, |_| 0.0);
+        assert!((next.angle - 1.0).abs() < 1e-6);
+        assert!((next.angular_vel - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rk4_step_integrates_constant_acceleration() {
+        let state = PendulumState::default();
+        let next = rk4_step(state, 1.0, |_| 1.0);
+        assert!((next.angle - 0.5).abs() < 1e-6);
+        assert!((next.angular_vel - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn output_value_is_zero_at_rest() {
+        let pendulum = SimplePhysics::new(NodeUuid::from(0), 0, 10.0, Vec2::ONE);
+        assert!(pendulum.output_value().length() < 1e-6);
+    }
+
+    #[test]
+    fn output_value_swings_negative_and_positive_around_rest() {
+        let mut pendulum = SimplePhysics::new(NodeUuid::from(0), 0, 10.0, Vec2::ONE);
+        pendulum.damping = 0.0;
+
+        // Displace the bob to one side of rest, then to the other; after
+        // a single step the signed output should follow that side.
+        pendulum.state.angle = 0.5;
+        pendulum.step(FIXED_TIMESTEP, Vec2::ZERO, 9.8);
+        assert!(pendulum.output_value().x > 0.0);
+
+        pendulum.reset();
+        pendulum.state.angle = -0.5;
+        pendulum.step(FIXED_TIMESTEP, Vec2::ZERO, 9.8);
+        assert!(pendulum.output_value().x < 0.0);
+    }
+
+    #[test]
+    fn reset_discards_velocity_and_returns_to_rest_angle() {
+        let mut pendulum = SimplePhysics::new(NodeUuid::from(0), 0, 10.0, Vec2::ONE);
+        pendulum.rest_angle = 0.3;
+        pendulum.state = PendulumState {
+            angle: 1.5,
+            angular_vel: 4.0,
+        };
+        pendulum.last_anchor_pos = Some(Vec2::new(1.0, 1.0));
+        pendulum.accumulator = 0.01;
+
+        pendulum.reset();
+
+        assert_eq!(pendulum.state.angle, pendulum.rest_angle);
+        assert_eq!(pendulum.state.angular_vel, 0.0);
+        assert_eq!(pendulum.last_anchor_pos, None);
+        assert_eq!(pendulum.accumulator, 0.0);
+    }
+
+    #[test]
+    fn spring_pendulum_accelerates_harder_back_toward_rest_than_plain_pendulum() {
+        let mut plain = SimplePhysics::new(NodeUuid::from(0), 0, 10.0, Vec2::ONE);
+        plain.damping = 0.0;
+        plain.state.angle = 0.5;
+
+        let mut spring = plain.clone();
+        spring.model = SimplePhysicsModel::SpringPendulum { spring_k: 2.0 };
+
+        let plain_accel = plain.angular_accel(plain.state, 9.8, Vec2::ZERO);
+        let spring_accel = spring.angular_accel(spring.state, 9.8, Vec2::ZERO);
+        assert!(spring_accel < plain_accel);
+    }
+}
@@ -0,0 +1,751 @@
+//! Compact binary (de)serialization for [`Puppet`].
+//!
+//! This is a `peek`/`poke` style codec rather than a self-describing
+//! format: [`Poke`] writes a type's fields into a growable buffer in a
+//! fixed little-endian layout, and [`Peek`] reads them back from a byte
+//! slice by advancing a cursor. It exists to cache parsed puppets and
+//! move them across IPC/network boundaries without re-parsing the full
+//! model container.
+
+use glam::Vec2;
+
+use crate::nodes::node::{Node, NodeUuid};
+use crate::nodes::node_tree::NodeTree;
+use crate::physics::{SimplePhysics, SimplePhysicsModel};
+use crate::puppet::{
+    Binding, BindingBase, InterpolateMode, Param, Puppet, PuppetAllowedModification,
+    PuppetAllowedRedistribution, PuppetAllowedUsers, PuppetMeta, PuppetPhysics, PuppetUsageRights,
+};
+
+/// Writes a value's fields into `buf` in a fixed little-endian layout.
+pub trait Poke {
+    fn poke(&self, buf: &mut Vec<u8>);
+}
+
+/// Reads a value back out of `buf`, advancing `cursor` past the bytes
+/// it consumed.
+///
+/// Like the format itself, this trait trusts its input: a truncated or
+/// corrupt buffer panics rather than returning an error. The one
+/// checked entry point is [`Puppet::from_bytes`], which validates the
+/// spec version before trusting the rest of the buffer.
+pub trait Peek: Sized {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self;
+}
+
+fn take<'a>(buf: &'a [u8], cursor: &mut usize, len: usize) -> &'a [u8] {
+    let slice = &buf[*cursor..*cursor + len];
+    *cursor += len;
+    slice
+}
+
+impl Poke for u8 {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        buf.push(*self);
+    }
+}
+
+impl Peek for u8 {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        take(buf, cursor, 1)[0]
+    }
+}
+
+impl Poke for bool {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        (*self as u8).poke(buf);
+    }
+}
+
+impl Peek for bool {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        u8::peek(buf, cursor) != 0
+    }
+}
+
+impl Poke for u32 {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl Peek for u32 {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        u32::from_le_bytes(take(buf, cursor, 4).try_into().unwrap())
+    }
+}
+
+impl Poke for f32 {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl Peek for f32 {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        f32::from_le_bytes(take(buf, cursor, 4).try_into().unwrap())
+    }
+}
+
+impl Poke for Vec2 {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.x.poke(buf);
+        self.y.poke(buf);
+    }
+}
+
+impl Peek for Vec2 {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        let x = f32::peek(buf, cursor);
+        let y = f32::peek(buf, cursor);
+        Vec2::new(x, y)
+    }
+}
+
+impl Poke for String {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        (self.len() as u32).poke(buf);
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Peek for String {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        let len = u32::peek(buf, cursor) as usize;
+        String::from_utf8(take(buf, cursor, len).to_vec()).expect("poke format: invalid utf-8")
+    }
+}
+
+impl Poke for Option<String> {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        match self {
+            Some(s) => {
+                true.poke(buf);
+                s.poke(buf);
+            }
+            None => false.poke(buf),
+        }
+    }
+}
+
+impl Peek for Option<String> {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        if bool::peek(buf, cursor) {
+            Some(String::peek(buf, cursor))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Poke> Poke for Vec<T> {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        (self.len() as u32).poke(buf);
+        for item in self {
+            item.poke(buf);
+        }
+    }
+}
+
+impl<T: Peek> Peek for Vec<T> {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        let len = u32::peek(buf, cursor) as usize;
+        (0..len).map(|_| T::peek(buf, cursor)).collect()
+    }
+}
+
+impl Poke for NodeUuid {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        u32::from(*self).poke(buf);
+    }
+}
+
+impl Peek for NodeUuid {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        NodeUuid::from(u32::peek(buf, cursor))
+    }
+}
+
+impl Poke for PuppetAllowedUsers {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            PuppetAllowedUsers::OnlyAuthor => 0,
+            PuppetAllowedUsers::OnlyLicensee => 1,
+            PuppetAllowedUsers::Everyone => 2,
+        };
+        tag.poke(buf);
+    }
+}
+
+impl Peek for PuppetAllowedUsers {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        match u8::peek(buf, cursor) {
+            0 => PuppetAllowedUsers::OnlyAuthor,
+            1 => PuppetAllowedUsers::OnlyLicensee,
+            2 => PuppetAllowedUsers::Everyone,
+            tag => panic!("poke format: unknown PuppetAllowedUsers tag {tag}"),
+        }
+    }
+}
+
+impl Poke for PuppetAllowedRedistribution {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            PuppetAllowedRedistribution::Prohibited => 0,
+            PuppetAllowedRedistribution::ViralLicense => 1,
+            PuppetAllowedRedistribution::CopyleftLicense => 2,
+        };
+        tag.poke(buf);
+    }
+}
+
+impl Peek for PuppetAllowedRedistribution {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        match u8::peek(buf, cursor) {
+            0 => PuppetAllowedRedistribution::Prohibited,
+            1 => PuppetAllowedRedistribution::ViralLicense,
+            2 => PuppetAllowedRedistribution::CopyleftLicense,
+            tag => panic!("poke format: unknown PuppetAllowedRedistribution tag {tag}"),
+        }
+    }
+}
+
+impl Poke for PuppetAllowedModification {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            PuppetAllowedModification::Prohibited => 0,
+            PuppetAllowedModification::AllowPersonal => 1,
+            PuppetAllowedModification::AllowRedistribute => 2,
+        };
+        tag.poke(buf);
+    }
+}
+
+impl Peek for PuppetAllowedModification {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        match u8::peek(buf, cursor) {
+            0 => PuppetAllowedModification::Prohibited,
+            1 => PuppetAllowedModification::AllowPersonal,
+            2 => PuppetAllowedModification::AllowRedistribute,
+            tag => panic!("poke format: unknown PuppetAllowedModification tag {tag}"),
+        }
+    }
+}
+
+impl Poke for PuppetUsageRights {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.allowed_users.poke(buf);
+        self.allow_violence.poke(buf);
+        self.allow_sexual.poke(buf);
+        self.allow_commercial.poke(buf);
+        self.allow_redistribution.poke(buf);
+        self.allow_modification.poke(buf);
+        self.require_attribution.poke(buf);
+    }
+}
+
+impl Peek for PuppetUsageRights {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            allowed_users: PuppetAllowedUsers::peek(buf, cursor),
+            allow_violence: bool::peek(buf, cursor),
+            allow_sexual: bool::peek(buf, cursor),
+            allow_commercial: bool::peek(buf, cursor),
+            allow_redistribution: PuppetAllowedRedistribution::peek(buf, cursor),
+            allow_modification: PuppetAllowedModification::peek(buf, cursor),
+            require_attribution: bool::peek(buf, cursor),
+        }
+    }
+}
+
+impl Poke for Option<PuppetUsageRights> {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        match self {
+            Some(rights) => {
+                true.poke(buf);
+                rights.poke(buf);
+            }
+            None => false.poke(buf),
+        }
+    }
+}
+
+impl Peek for Option<PuppetUsageRights> {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        if bool::peek(buf, cursor) {
+            Some(PuppetUsageRights::peek(buf, cursor))
+        } else {
+            None
+        }
+    }
+}
+
+impl Poke for Option<u32> {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        match self {
+            Some(v) => {
+                true.poke(buf);
+                v.poke(buf);
+            }
+            None => false.poke(buf),
+        }
+    }
+}
+
+impl Peek for Option<u32> {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        if bool::peek(buf, cursor) {
+            Some(u32::peek(buf, cursor))
+        } else {
+            None
+        }
+    }
+}
+
+impl Poke for PuppetMeta {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.name.poke(buf);
+        self.version.poke(buf);
+        self.rigger.poke(buf);
+        self.artist.poke(buf);
+        self.rights.poke(buf);
+        self.copyright.poke(buf);
+        self.license_url.poke(buf);
+        self.contact.poke(buf);
+        self.reference.poke(buf);
+        self.thumbnail_id.poke(buf);
+        self.preserve_pixels.poke(buf);
+    }
+}
+
+impl Peek for PuppetMeta {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            name: Option::<String>::peek(buf, cursor),
+            version: String::peek(buf, cursor),
+            rigger: Option::<String>::peek(buf, cursor),
+            artist: Option::<String>::peek(buf, cursor),
+            rights: Option::<PuppetUsageRights>::peek(buf, cursor),
+            copyright: Option::<String>::peek(buf, cursor),
+            license_url: Option::<String>::peek(buf, cursor),
+            contact: Option::<String>::peek(buf, cursor),
+            reference: Option::<String>::peek(buf, cursor),
+            thumbnail_id: Option::<u32>::peek(buf, cursor),
+            preserve_pixels: bool::peek(buf, cursor),
+        }
+    }
+}
+
+impl Poke for PuppetPhysics {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.pixels_per_meter.poke(buf);
+        self.gravity.poke(buf);
+    }
+}
+
+impl Peek for PuppetPhysics {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            pixels_per_meter: f32::peek(buf, cursor),
+            gravity: f32::peek(buf, cursor),
+        }
+    }
+}
+
+impl Poke for InterpolateMode {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            InterpolateMode::Linear => 0,
+            InterpolateMode::Cubic => 1,
+        };
+        tag.poke(buf);
+    }
+}
+
+impl Peek for InterpolateMode {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        match u8::peek(buf, cursor) {
+            0 => InterpolateMode::Linear,
+            1 => InterpolateMode::Cubic,
+            tag => panic!("poke format: unknown InterpolateMode tag {tag}"),
+        }
+    }
+}
+
+impl Poke for BindingBase {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.node.poke(buf);
+        self.is_set.poke(buf);
+        self.interpolate_mode.poke(buf);
+    }
+}
+
+impl Peek for BindingBase {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            node: NodeUuid::peek(buf, cursor),
+            is_set: Vec::<Vec<bool>>::peek(buf, cursor),
+            interpolate_mode: InterpolateMode::peek(buf, cursor),
+        }
+    }
+}
+
+impl Poke for Binding {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        macro_rules! poke_variant {
+            ($tag:expr, $base:expr, $values:expr) => {{
+                ($tag as u8).poke(buf);
+                $base.poke(buf);
+                $values.poke(buf);
+            }};
+        }
+
+        match self {
+            Binding::ZSort { base, values } => poke_variant!(0, base, values),
+            Binding::TransformTX { base, values } => poke_variant!(1, base, values),
+            Binding::TransformTY { base, values } => poke_variant!(2, base, values),
+            Binding::TransformSX { base, values } => poke_variant!(3, base, values),
+            Binding::TransformSY { base, values } => poke_variant!(4, base, values),
+            Binding::TransformRX { base, values } => poke_variant!(5, base, values),
+            Binding::TransformRY { base, values } => poke_variant!(6, base, values),
+            Binding::TransformRZ { base, values } => poke_variant!(7, base, values),
+            Binding::Deform { base, values } => poke_variant!(8, base, values),
+        }
+    }
+}
+
+impl Peek for Binding {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        let tag = u8::peek(buf, cursor);
+        let base = BindingBase::peek(buf, cursor);
+
+        macro_rules! scalar_variant {
+            ($variant:ident) => {
+                Binding::$variant {
+                    base,
+                    values: Vec::<Vec<f32>>::peek(buf, cursor),
+                }
+            };
+        }
+
+        match tag {
+            0 => scalar_variant!(ZSort),
+            1 => scalar_variant!(TransformTX),
+            2 => scalar_variant!(TransformTY),
+            3 => scalar_variant!(TransformSX),
+            4 => scalar_variant!(TransformSY),
+            5 => scalar_variant!(TransformRX),
+            6 => scalar_variant!(TransformRY),
+            7 => scalar_variant!(TransformRZ),
+            8 => Binding::Deform {
+                base,
+                values: Vec::<Vec<Vec<Vec2>>>::peek(buf, cursor),
+            },
+            tag => panic!("poke format: unknown Binding tag {tag}"),
+        }
+    }
+}
+
+impl Poke for Param {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.uuid.poke(buf);
+        self.name.poke(buf);
+        self.is_vec2.poke(buf);
+        self.min.poke(buf);
+        self.max.poke(buf);
+        self.defaults.poke(buf);
+        self.axis_points[0].poke(buf);
+        self.axis_points[1].poke(buf);
+        self.bindings.poke(buf);
+    }
+}
+
+impl Peek for Param {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            uuid: u32::peek(buf, cursor),
+            name: String::peek(buf, cursor),
+            is_vec2: bool::peek(buf, cursor),
+            min: Vec2::peek(buf, cursor),
+            max: Vec2::peek(buf, cursor),
+            defaults: Vec2::peek(buf, cursor),
+            axis_points: [Vec::<f32>::peek(buf, cursor), Vec::<f32>::peek(buf, cursor)],
+            bindings: Vec::<Binding>::peek(buf, cursor),
+        }
+    }
+}
+
+impl Poke for SimplePhysicsModel {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        match self {
+            SimplePhysicsModel::Pendulum => 0u8.poke(buf),
+            SimplePhysicsModel::SpringPendulum { spring_k } => {
+                1u8.poke(buf);
+                spring_k.poke(buf);
+            }
+        }
+    }
+}
+
+impl Peek for SimplePhysicsModel {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        match u8::peek(buf, cursor) {
+            0 => SimplePhysicsModel::Pendulum,
+            1 => SimplePhysicsModel::SpringPendulum {
+                spring_k: f32::peek(buf, cursor),
+            },
+            tag => panic!("poke format: unknown SimplePhysicsModel tag {tag}"),
+        }
+    }
+}
+
+impl Poke for SimplePhysics {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.anchor.poke(buf);
+        (self.param as u32).poke(buf);
+        self.length.poke(buf);
+        self.damping.poke(buf);
+        self.rest_angle.poke(buf);
+        self.model.poke(buf);
+        self.output_scale.poke(buf);
+    }
+}
+
+impl Peek for SimplePhysics {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        let anchor = NodeUuid::peek(buf, cursor);
+        let param = u32::peek(buf, cursor) as usize;
+        let length = f32::peek(buf, cursor);
+        let damping = f32::peek(buf, cursor);
+        let rest_angle = f32::peek(buf, cursor);
+        let model = SimplePhysicsModel::peek(buf, cursor);
+        let output_scale = Vec2::peek(buf, cursor);
+
+        let mut physics = SimplePhysics::new(anchor, param, length, output_scale);
+        physics.damping = damping;
+        physics.rest_angle = rest_angle;
+        physics.model = model;
+        physics
+    }
+}
+
+impl Poke for Node {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.uuid.poke(buf);
+        self.position.poke(buf);
+        self.offset_tx.poke(buf);
+        self.offset_ty.poke(buf);
+        self.offset_sx.poke(buf);
+        self.offset_sy.poke(buf);
+        self.offset_rx.poke(buf);
+        self.offset_ry.poke(buf);
+        self.offset_rz.poke(buf);
+        self.zsort_offset.poke(buf);
+        self.deform_offset.poke(buf);
+        self.is_drawable.poke(buf);
+        self.vertices.poke(buf);
+        self.uvs.poke(buf);
+        self.indices.poke(buf);
+        self.texture_id.poke(buf);
+    }
+}
+
+impl Peek for Node {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            uuid: NodeUuid::peek(buf, cursor),
+            position: Vec2::peek(buf, cursor),
+            offset_tx: f32::peek(buf, cursor),
+            offset_ty: f32::peek(buf, cursor),
+            offset_sx: f32::peek(buf, cursor),
+            offset_sy: f32::peek(buf, cursor),
+            offset_rx: f32::peek(buf, cursor),
+            offset_ry: f32::peek(buf, cursor),
+            offset_rz: f32::peek(buf, cursor),
+            zsort_offset: f32::peek(buf, cursor),
+            deform_offset: Vec::<Vec2>::peek(buf, cursor),
+            is_drawable: bool::peek(buf, cursor),
+            vertices: Vec::<Vec2>::peek(buf, cursor),
+            uvs: Vec::<Vec2>::peek(buf, cursor),
+            indices: Vec::<u32>::peek(buf, cursor),
+            texture_id: Option::<u32>::peek(buf, cursor),
+        }
+    }
+}
+
+impl Poke for NodeTree {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.nodes.poke(buf);
+    }
+}
+
+impl Peek for NodeTree {
+    fn peek(buf: &[u8], cursor: &mut usize) -> Self {
+        Self {
+            nodes: Vec::<Node>::peek(buf, cursor),
+        }
+    }
+}
+
+/// A [`Puppet`] buffer failed to decode.
+#[derive(Debug)]
+pub enum PeekError {
+    /// The buffer's spec version doesn't match
+    /// [`crate::INOCHI2D_SPEC_VERSION`], so the rest of the layout can't
+    /// be trusted.
+    VersionMismatch { expected: String, found: String },
+}
+
+impl Poke for Puppet {
+    fn poke(&self, buf: &mut Vec<u8>) {
+        self.meta.poke(buf);
+        self.physics.poke(buf);
+        self.nodes.poke(buf);
+        self.parameters.poke(buf);
+        self.simple_physics.poke(buf);
+    }
+}
+
+impl Puppet {
+    /// Encodes this puppet into the compact binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.poke(&mut buf);
+        buf
+    }
+
+    /// Decodes a puppet previously written with [`Puppet::to_bytes`],
+    /// rejecting buffers whose `meta.version` doesn't match the spec
+    /// version this build of the crate understands.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, PeekError> {
+        let mut cursor = 0;
+        let meta = PuppetMeta::peek(buf, &mut cursor);
+        if meta.version != crate::INOCHI2D_SPEC_VERSION {
+            return Err(PeekError::VersionMismatch {
+                expected: crate::INOCHI2D_SPEC_VERSION.to_owned(),
+                found: meta.version,
+            });
+        }
+
+        Ok(Self {
+            meta,
+            physics: PuppetPhysics::peek(buf, &mut cursor),
+            nodes: crate::nodes::node_tree::NodeTree::peek(buf, &mut cursor),
+            parameters: Vec::<Param>::peek(buf, &mut cursor),
+            simple_physics: Vec::<SimplePhysics>::peek(buf, &mut cursor),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_node() -> Node {
+        Node {
+            uuid: NodeUuid::from(0),
+            position: Vec2::new(1.0, 2.0),
+            offset_tx: 0.1,
+            offset_ty: 0.2,
+            offset_sx: 1.0,
+            offset_sy: 1.0,
+            offset_rx: 0.0,
+            offset_ry: 0.0,
+            offset_rz: 0.0,
+            zsort_offset: 0.0,
+            deform_offset: vec![Vec2::ZERO, Vec2::new(0.5, -0.5)],
+            is_drawable: true,
+            vertices: vec![Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)],
+            uvs: vec![Vec2::ZERO, Vec2::X, Vec2::Y],
+            indices: vec![0, 1, 2],
+            texture_id: Some(4),
+        }
+    }
+
+    fn sample_param() -> Param {
+        Param {
+            uuid: 1,
+            name: "blink".to_owned(),
+            is_vec2: false,
+            min: Vec2::new(0.0, 0.0),
+            max: Vec2::new(1.0, 0.0),
+            defaults: Vec2::ZERO,
+            axis_points: [vec![0.0, 1.0], Vec::new()],
+            bindings: vec![Binding::TransformTY {
+                base: BindingBase {
+                    node: NodeUuid::from(0),
+                    is_set: vec![vec![true], vec![true]],
+                    interpolate_mode: InterpolateMode::Cubic,
+                },
+                values: vec![vec![0.0], vec![10.0]],
+            }],
+        }
+    }
+
+    fn sample_puppet() -> Puppet {
+        Puppet {
+            meta: PuppetMeta {
+                name: Some("Test Puppet".to_owned()),
+                ..Default::default()
+            },
+            physics: PuppetPhysics {
+                pixels_per_meter: 100.0,
+                gravity: 9.8,
+            },
+            nodes: NodeTree {
+                nodes: vec![sample_node()],
+            },
+            parameters: vec![sample_param()],
+            simple_physics: vec![SimplePhysics::new(
+                NodeUuid::from(0),
+                0,
+                50.0,
+                Vec2::new(1.0, 1.0),
+            )],
+        }
+    }
+
+    #[test]
+    fn puppet_round_trips_through_bytes() {
+        let puppet = sample_puppet();
+        let bytes = puppet.to_bytes();
+        let decoded = Puppet::from_bytes(&bytes).expect("decode should succeed");
+        assert_eq!(puppet, decoded);
+    }
+
+    #[test]
+    fn from_bytes_rejects_mismatched_spec_version() {
+        let mut puppet = sample_puppet();
+        puppet.meta.version = "not-a-real-spec-version".to_owned();
+        let bytes = puppet.to_bytes();
+
+        let err = Puppet::from_bytes(&bytes).expect_err("mismatched version should be rejected");
+        match err {
+            PeekError::VersionMismatch { found, .. } => {
+                assert_eq!(found, "not-a-real-spec-version");
+            }
+        }
+    }
+
+    #[test]
+    fn node_round_trips() {
+        let node = sample_node();
+        let mut buf = Vec::new();
+        node.poke(&mut buf);
+
+        let mut cursor = 0;
+        assert_eq!(node, Node::peek(&buf, &mut cursor));
+        assert_eq!(cursor, buf.len());
+    }
+
+    #[test]
+    fn param_round_trips() {
+        let param = sample_param();
+        let mut buf = Vec::new();
+        param.poke(&mut buf);
+
+        let mut cursor = 0;
+        assert_eq!(param, Param::peek(&buf, &mut cursor));
+        assert_eq!(cursor, buf.len());
+    }
+}
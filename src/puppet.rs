@@ -1,12 +1,15 @@
 #![allow(dead_code)]
 
+use std::ops::{Add, Mul, Sub};
+
 use glam::Vec2;
 
 use crate::nodes::node::NodeUuid;
 use crate::nodes::node_tree::NodeTree;
+use crate::physics::SimplePhysics;
 
 /// Who is allowed to use the puppet?
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum PuppetAllowedUsers {
     /// Only the author(s) are allowed to use the puppet.
     #[default]
@@ -18,7 +21,7 @@ pub enum PuppetAllowedUsers {
 }
 
 /// Can the puppet be redistributed?
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum PuppetAllowedRedistribution {
     /// Redistribution is prohibited
     #[default]
@@ -34,7 +37,7 @@ pub enum PuppetAllowedRedistribution {
 }
 
 /// Can the puppet be modified?
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum PuppetAllowedModification {
     /// Modification is prohibited
     #[default]
@@ -47,7 +50,7 @@ pub enum PuppetAllowedModification {
 }
 
 /// Terms of usage of the puppet.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct PuppetUsageRights {
     /// Who is allowed to use the puppet?
     pub allowed_users: PuppetAllowedUsers,
@@ -66,7 +69,7 @@ pub struct PuppetUsageRights {
 }
 
 /// Puppet meta information.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PuppetMeta {
     /// Name of the puppet.
     pub name: Option<String>,
@@ -111,25 +114,28 @@ impl Default for PuppetMeta {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PuppetPhysics {
-    pixels_per_meter: f32,
-    gravity: f32,
+    pub(crate) pixels_per_meter: f32,
+    pub(crate) gravity: f32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum InterpolateMode {
     Linear,
+    /// Cubic Hermite interpolation with Catmull-Rom tangents, for
+    /// smoother motion between keypoints than [`InterpolateMode::Linear`].
+    Cubic,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct BindingBase {
-    node: NodeUuid,
-    is_set: Vec<Vec<bool>>,
-    interpolate_mode: InterpolateMode,
+    pub(crate) node: NodeUuid,
+    pub(crate) is_set: Vec<Vec<bool>>,
+    pub(crate) interpolate_mode: InterpolateMode,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Binding {
     ZSort {
         base: BindingBase,
@@ -169,22 +175,482 @@ pub enum Binding {
     },
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Param {
-    uuid: u32,
-    name: String,
-    is_vec2: bool,
-    min: Vec2,
-    max: Vec2,
-    defaults: Vec2,
-    axis_points: [Vec<f32>; 2],
-    bindings: Vec<Binding>,
+    pub(crate) uuid: u32,
+    pub(crate) name: String,
+    pub(crate) is_vec2: bool,
+    pub(crate) min: Vec2,
+    pub(crate) max: Vec2,
+    pub(crate) defaults: Vec2,
+    pub(crate) axis_points: [Vec<f32>; 2],
+    pub(crate) bindings: Vec<Binding>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Puppet {
     pub meta: PuppetMeta,
     pub physics: PuppetPhysics,
     pub nodes: NodeTree,
     pub parameters: Vec<Param>,
+    /// `SimplePhysics` pendulum/spring nodes driving some of `parameters`.
+    pub simple_physics: Vec<SimplePhysics>,
+}
+
+impl Puppet {
+    /// Advances every `SimplePhysics` node by `dt` seconds and applies
+    /// its resulting parameter value to the node tree, so bindings that
+    /// depend on physics-driven parameters animate accordingly.
+    pub fn update_physics(&mut self, dt: f32) {
+        let gravity_accel = self.physics.gravity * self.physics.pixels_per_meter;
+
+        // Bindings accumulate onto a node's offsets so that multiple
+        // bindings targeting the same node add together within one
+        // pass; reset to baseline first so this pass doesn't pile onto
+        // whatever the previous pass left behind.
+        self.nodes.reset_offsets();
+
+        for simple in &mut self.simple_physics {
+            let Some(anchor_pos) = self.nodes.world_position(simple.anchor) else {
+                continue;
+            };
+            simple.step(dt, anchor_pos, gravity_accel);
+
+            let Some(param) = self.parameters.get(simple.param) else {
+                continue;
+            };
+            // `output_value` is signed (it swings around 0 as the bob
+            // passes either side of rest), so clamp to [-1, 1] and remap
+            // around the parameter's center rather than clamping to
+            // [0, 1], which would discard the entire negative half of
+            // the swing.
+            let t = simple.output_value().clamp(Vec2::NEG_ONE, Vec2::ONE);
+            let center = (param.max + param.min) * 0.5;
+            let half_range = (param.max - param.min) * 0.5;
+            let value = center + half_range * t;
+            param.apply(value, &mut self.nodes);
+        }
+    }
+}
+
+impl Param {
+    /// Evaluates this parameter at `value` and applies every binding's
+    /// interpolated output to the node it targets.
+    ///
+    /// `value` is clamped to `[min, max]` on each axis before the
+    /// bracketing keypoint interval is located, so callers don't need to
+    /// clamp themselves.
+    pub fn apply(&self, value: Vec2, tree: &mut NodeTree) {
+        let value = value.clamp(self.min, self.max);
+
+        let (ix, tx) = axis_cell(&self.axis_points[0], value.x);
+        let (iy, ty) = if self.is_vec2 {
+            axis_cell(&self.axis_points[1], value.y)
+        } else {
+            (0, 0.0)
+        };
+
+        for binding in &self.bindings {
+            binding.apply(tree, ix, tx, iy, ty, self.is_vec2);
+        }
+    }
+}
+
+/// Locates the keypoint interval on a single axis that brackets `v` and
+/// the normalized factor within it, clamping `v` to the axis range.
+///
+/// Returns the index of the lower keypoint and a factor in `[0, 1]`. An
+/// axis with fewer than two keypoints always yields `(0, 0.0)`.
+fn axis_cell(points: &[f32], v: f32) -> (usize, f32) {
+    if points.len() < 2 {
+        return (0, 0.0);
+    }
+    if v <= points[0] {
+        return (0, 0.0);
+    }
+    let last = points.len() - 1;
+    if v >= points[last] {
+        return (last - 1, 1.0);
+    }
+    for i in 0..last {
+        if v >= points[i] && v <= points[i + 1] {
+            let span = points[i + 1] - points[i];
+            let t = if span.abs() > f32::EPSILON {
+                (v - points[i]) / span
+            } else {
+                0.0
+            };
+            return (i, t);
+        }
+    }
+    (last - 1, 1.0)
+}
+
+/// Returns `(i, j)` if it is set; otherwise the closest set keypoint in
+/// row `is_set[i]`, or, if that row has none, the closest set keypoint
+/// in column `j`, so unset keypoints never leave the grid ill-defined.
+/// A set keypoint in the row is preferred even when one in the column
+/// is closer; if neither holds a set keypoint, `(i, j)` is returned.
+fn nearest_set_keypoint(is_set: &[Vec<bool>], i: usize, j: usize) -> (usize, usize) {
+    if is_set[i][j] {
+        return (i, j);
+    }
+
+    let row = &is_set[i];
+    let nearest_in_row = row
+        .iter()
+        .enumerate()
+        .filter(|(_, &set)| set)
+        .min_by_key(|(k, _)| k.abs_diff(j));
+    if let Some((k, _)) = nearest_in_row {
+        return (i, k);
+    }
+
+    let nearest_in_col = is_set
+        .iter()
+        .enumerate()
+        .filter(|(_, col)| col[j])
+        .min_by_key(|(k, _)| k.abs_diff(i));
+    if let Some((k, _)) = nearest_in_col {
+        return (k, j);
+    }
+
+    (i, j)
+}
+
+/// Clamps `i` into `[0, len - 1]`, used to degrade cubic boundary
+/// segments gracefully when a Catmull-Rom neighbor falls outside the
+/// keypoint grid.
+fn clamp_idx(i: isize, len: usize) -> usize {
+    i.clamp(0, len as isize - 1) as usize
+}
+
+/// Linearly interpolates between `a` and `b`.
+fn lerp<T>(a: T, b: T, t: f32) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    a + (b - a) * t
+}
+
+/// Cubic Hermite interpolation between `p1` and `p2` at factor `t`,
+/// using Catmull-Rom tangents derived from the neighboring keypoints
+/// `p0` and `p3`.
+fn cubic_hermite<T>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    let m1 = (p2 - p0) * 0.5;
+    let m2 = (p3 - p1) * 0.5;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+    p1 * h00 + m1 * h10 + p2 * h01 + m2 * h11
+}
+
+/// Blends the four samples bracketing a keypoint interval according to
+/// `mode`: a linear blend of `p[1]`/`p[2]` for [`InterpolateMode::Linear`],
+/// or a Catmull-Rom Hermite segment across all four for
+/// [`InterpolateMode::Cubic`].
+fn blend_1d<T>(p: [T; 4], t: f32, mode: &InterpolateMode) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    match mode {
+        InterpolateMode::Linear => lerp(p[1], p[2], t),
+        InterpolateMode::Cubic => cubic_hermite(p[0], p[1], p[2], p[3], t),
+    }
+}
+
+/// Interpolates the keypoints of a scalar binding grid around the cell
+/// located by [`axis_cell`] on each axis, falling back to the nearest
+/// set keypoint for any corner that `is_set` marks as unset. For 2D
+/// parameters this is applied tensor-product style on both axes.
+#[allow(clippy::too_many_arguments)]
+fn sample_scalar_grid(
+    values: &[Vec<f32>],
+    is_set: &[Vec<bool>],
+    ix: usize,
+    tx: f32,
+    iy: usize,
+    ty: f32,
+    is_vec2: bool,
+    mode: &InterpolateMode,
+) -> f32 {
+    let width = values.len();
+    let at = |i: isize, j: usize| -> f32 {
+        let (i, j) = nearest_set_keypoint(is_set, clamp_idx(i, width), j);
+        values[i][j]
+    };
+    let row = |j: usize| -> f32 {
+        let ix = ix as isize;
+        let p = [at(ix - 1, j), at(ix, j), at(ix + 1, j), at(ix + 2, j)];
+        blend_1d(p, tx, mode)
+    };
+
+    if !is_vec2 {
+        return row(0);
+    }
+
+    let height = values[0].len();
+    let clamp_j = |j: isize| clamp_idx(j, height);
+    let iy = iy as isize;
+    let p = [
+        row(clamp_j(iy - 1)),
+        row(clamp_j(iy)),
+        row(clamp_j(iy + 1)),
+        row(clamp_j(iy + 2)),
+    ];
+    blend_1d(p, ty, mode)
+}
+
+/// Same as [`sample_scalar_grid`], but for a `Deform` binding whose
+/// keypoints each hold a full `Vec<Vec2>` of per-vertex offsets;
+/// interpolation is applied independently per vertex.
+#[allow(clippy::too_many_arguments)]
+fn sample_deform_grid(
+    values: &[Vec<Vec<Vec2>>],
+    is_set: &[Vec<bool>],
+    ix: usize,
+    tx: f32,
+    iy: usize,
+    ty: f32,
+    is_vec2: bool,
+    mode: &InterpolateMode,
+    vertex_count: usize,
+) -> Vec<Vec2> {
+    let width = values.len();
+    let at = |i: isize, j: usize| -> &Vec<Vec2> {
+        let (i, j) = nearest_set_keypoint(is_set, clamp_idx(i, width), j);
+        &values[i][j]
+    };
+    let vert = |v: &[Vec2], k: usize| v.get(k).copied().unwrap_or_default();
+    let row = |j: usize| -> Vec<Vec2> {
+        let ix = ix as isize;
+        let (p0, p1, p2, p3) = (at(ix - 1, j), at(ix, j), at(ix + 1, j), at(ix + 2, j));
+        (0..vertex_count)
+            .map(|k| blend_1d([vert(p0, k), vert(p1, k), vert(p2, k), vert(p3, k)], tx, mode))
+            .collect()
+    };
+
+    if !is_vec2 {
+        return row(0);
+    }
+
+    let height = values[0].len();
+    let clamp_j = |j: isize| clamp_idx(j, height);
+    let iy = iy as isize;
+    let rows = [
+        row(clamp_j(iy - 1)),
+        row(clamp_j(iy)),
+        row(clamp_j(iy + 1)),
+        row(clamp_j(iy + 2)),
+    ];
+    (0..vertex_count)
+        .map(|k| blend_1d([rows[0][k], rows[1][k], rows[2][k], rows[3][k]], ty, mode))
+        .collect()
+}
+
+impl Binding {
+    fn base(&self) -> &BindingBase {
+        match self {
+            Binding::ZSort { base, .. }
+            | Binding::TransformTX { base, .. }
+            | Binding::TransformTY { base, .. }
+            | Binding::TransformSX { base, .. }
+            | Binding::TransformSY { base, .. }
+            | Binding::TransformRX { base, .. }
+            | Binding::TransformRY { base, .. }
+            | Binding::TransformRZ { base, .. }
+            | Binding::Deform { base, .. } => base,
+        }
+    }
+
+    /// Interpolates this binding's grid at the cell located by
+    /// [`axis_cell`] on each axis and writes the result onto the node
+    /// it targets.
+    fn apply(&self, tree: &mut NodeTree, ix: usize, tx: f32, iy: usize, ty: f32, is_vec2: bool) {
+        let base = self.base();
+        let Some(node) = tree.get_mut(base.node) else {
+            return;
+        };
+
+        macro_rules! apply_scalar {
+            ($values:expr, $field:ident) => {{
+                let v = sample_scalar_grid(
+                    $values,
+                    &base.is_set,
+                    ix,
+                    tx,
+                    iy,
+                    ty,
+                    is_vec2,
+                    &base.interpolate_mode,
+                );
+                node.$field += v;
+            }};
+        }
+
+        match self {
+            Binding::ZSort { values, .. } => apply_scalar!(values, zsort_offset),
+            Binding::TransformTX { values, .. } => apply_scalar!(values, offset_tx),
+            Binding::TransformTY { values, .. } => apply_scalar!(values, offset_ty),
+            Binding::TransformSX { values, .. } => apply_scalar!(values, offset_sx),
+            Binding::TransformSY { values, .. } => apply_scalar!(values, offset_sy),
+            Binding::TransformRX { values, .. } => apply_scalar!(values, offset_rx),
+            Binding::TransformRY { values, .. } => apply_scalar!(values, offset_ry),
+            Binding::TransformRZ { values, .. } => apply_scalar!(values, offset_rz),
+            Binding::Deform { values, .. } => {
+                let offsets = sample_deform_grid(
+                    values,
+                    &base.is_set,
+                    ix,
+                    tx,
+                    iy,
+                    ty,
+                    is_vec2,
+                    &base.interpolate_mode,
+                    node.deform_offset.len(),
+                );
+                for (slot, offset) in node.deform_offset.iter_mut().zip(offsets) {
+                    *slot += offset;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_cell_clamps_below_range() {
+        assert_eq!(axis_cell(&[0.0, 1.0, 2.0], -5.0), (0, 0.0));
+    }
+
+    #[test]
+    fn axis_cell_clamps_above_range() {
+        assert_eq!(axis_cell(&[0.0, 1.0, 2.0], 50.0), (1, 1.0));
+    }
+
+    #[test]
+    fn axis_cell_finds_bracketing_interval() {
+        let (i, t) = axis_cell(&[0.0, 1.0, 2.0, 4.0], 3.0);
+        assert_eq!(i, 2);
+        assert!((t - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn axis_cell_single_point_is_degenerate() {
+        assert_eq!(axis_cell(&[1.0], 5.0), (0, 0.0));
+    }
+
+    #[test]
+    fn nearest_set_keypoint_returns_self_when_set() {
+        let is_set = vec![vec![true, true], vec![true, true]];
+        assert_eq!(nearest_set_keypoint(&is_set, 1, 1), (1, 1));
+    }
+
+    #[test]
+    fn nearest_set_keypoint_falls_back_along_row() {
+        let is_set = vec![vec![true, false, false]];
+        assert_eq!(nearest_set_keypoint(&is_set, 0, 2), (0, 0));
+    }
+
+    #[test]
+    fn nearest_set_keypoint_falls_back_along_column() {
+        let is_set = vec![vec![false], vec![true]];
+        assert_eq!(nearest_set_keypoint(&is_set, 0, 0), (1, 0));
+    }
+
+    #[test]
+    fn sample_scalar_grid_bilinear_interpolates_all_four_corners() {
+        let values = vec![vec![0.0, 10.0], vec![20.0, 30.0]];
+        let is_set = vec![vec![true, true], vec![true, true]];
+        let v = sample_scalar_grid(
+            &values,
+            &is_set,
+            0,
+            0.5,
+            0,
+            0.5,
+            true,
+            &InterpolateMode::Linear,
+        );
+        assert!((v - 15.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cubic_hermite_passes_through_keypoints() {
+        assert!((cubic_hermite(0.0, 1.0, 2.0, 3.0, 0.0) - 1.0).abs() < 1e-6);
+        assert!((cubic_hermite(0.0, 1.0, 2.0, 3.0, 1.0) - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cubic_hermite_is_linear_along_a_straight_line() {
+        // Evenly spaced collinear points have zero curvature, so the
+        // Catmull-Rom tangents reduce cubic interpolation to the same
+        // straight line a linear blend would produce.
+        let (p0, p1, p2, p3) = (0.0, 1.0, 2.0, 3.0);
+        for i in 0..=4 {
+            let t = i as f32 / 4.0;
+            let cubic = cubic_hermite(p0, p1, p2, p3, t);
+            let linear = lerp(p1, p2, t);
+            assert!((cubic - linear).abs() < 1e-5, "t={t} cubic={cubic} linear={linear}");
+        }
+    }
+
+    #[test]
+    fn blend_1d_dispatches_on_mode() {
+        let p = [0.0, 1.0, 2.0, 3.0];
+        assert!((blend_1d(p, 0.5, &InterpolateMode::Linear) - 1.5).abs() < 1e-6);
+        assert!((blend_1d(p, 0.5, &InterpolateMode::Cubic) - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sample_scalar_grid_cubic_1d_clamps_boundary_neighbors() {
+        // With only two keypoints the missing Catmull-Rom neighbors
+        // clamp to the endpoints, so cubic mode should degrade to the
+        // same result as linear mode at the interval's midpoint.
+        let values = vec![vec![0.0], vec![10.0]];
+        let is_set = vec![vec![true], vec![true]];
+        let linear = sample_scalar_grid(&values, &is_set, 0, 0.5, 0, 0.0, false, &InterpolateMode::Linear);
+        let cubic = sample_scalar_grid(&values, &is_set, 0, 0.5, 0, 0.0, false, &InterpolateMode::Cubic);
+        assert!((linear - cubic).abs() < 1e-6);
+    }
+
+    #[test]
+    fn param_apply_does_not_accumulate_across_reset_passes() {
+        let mut tree = NodeTree {
+            nodes: vec![crate::nodes::node::Node::default()],
+        };
+
+        let param = Param {
+            uuid: 0,
+            name: "test".to_owned(),
+            is_vec2: false,
+            min: Vec2::new(0.0, 0.0),
+            max: Vec2::new(1.0, 0.0),
+            defaults: Vec2::ZERO,
+            axis_points: [vec![0.0, 1.0], Vec::new()],
+            bindings: vec![Binding::TransformTY {
+                base: BindingBase {
+                    node: NodeUuid::from(0),
+                    is_set: vec![vec![true], vec![true]],
+                    interpolate_mode: InterpolateMode::Linear,
+                },
+                values: vec![vec![0.0], vec![10.0]],
+            }],
+        };
+
+        for _ in 0..3 {
+            tree.reset_offsets();
+            param.apply(Vec2::new(1.0, 0.0), &mut tree);
+            assert_eq!(tree.get(NodeUuid::from(0)).unwrap().offset_ty, 10.0);
+        }
+    }
 }
@@ -0,0 +1,8 @@
+//! GPU rendering backends for [`Puppet`](crate::puppet::Puppet).
+//!
+//! This module is optional: it pulls in a GPU API as a dependency, so
+//! consumers that only need to load and evaluate puppets (e.g. a
+//! headless rigging tool) don't pay for it.
+
+#[cfg(feature = "wgpu")]
+pub mod wgpu;
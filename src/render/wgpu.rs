@@ -0,0 +1,556 @@
+//! `wgpu`-backed renderer: a compute pass applies each node's
+//! accumulated transform and deform offsets to its mesh, then a
+//! lightweight raster pass alpha-blends the drawables in ascending
+//! `ZSort` order, each one drawn over those before it.
+
+use wgpu::util::DeviceExt;
+
+use crate::nodes::node::NodeUuid;
+use crate::puppet::Puppet;
+
+/// A GPU-side mesh vertex: rest position and texture coordinate. Node
+/// transforms and deform offsets are applied in the compute pass rather
+/// than baked in here, so this buffer only needs re-uploading when the
+/// mesh topology itself changes.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Vertex {
+    position: [f32; 2],
+    uv: [f32; 2],
+}
+
+/// Per-node uniform consumed by the transform compute shader: the
+/// accumulated `Transform*` binding outputs plus the vertex range this
+/// node owns in the shared vertex buffer.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct NodeUniform {
+    translation: [f32; 2],
+    scale: [f32; 2],
+    rotation: [f32; 3],
+    vertex_offset: u32,
+    vertex_count: u32,
+    texture_index: u32,
+    // WGSL gives a `vec3<f32>` field align 16, which raises this
+    // struct's own alignment to 16 and so rounds its size -- and its
+    // `array<NodeUniform>` stride -- up to 48 bytes. The fields above
+    // only occupy 40, so two trailing
+    // `u32`s of padding are needed to match, not one.
+    _pad: [u32; 2],
+}
+
+/// Persistent GPU state for one drawable node: its slice of the shared
+/// vertex/deform buffers and the texture it samples.
+struct DrawableGpu {
+    node: NodeUuid,
+    vertex_offset: u32,
+    vertex_count: u32,
+    index_offset: u32,
+    index_count: u32,
+    texture_index: u32,
+}
+
+fn storage_entry(binding: u32, visibility: wgpu::ShaderStages, read_only: bool) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+/// Renders a [`Puppet`] with `wgpu`, applying node transforms and
+/// `Deform` offsets on the GPU and honoring [`PuppetMeta::preserve_pixels`](crate::puppet::PuppetMeta::preserve_pixels)
+/// for pixel-art puppets.
+pub struct Renderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    target_format: wgpu::TextureFormat,
+
+    compute_bind_group_layout: wgpu::BindGroupLayout,
+    raster_bind_group_layout: wgpu::BindGroupLayout,
+    compute_pipeline: wgpu::ComputePipeline,
+    render_pipeline: wgpu::RenderPipeline,
+
+    nearest_sampler: wgpu::Sampler,
+    linear_sampler: wgpu::Sampler,
+
+    vertex_buffer: Option<wgpu::Buffer>,
+    transformed_buffer: Option<wgpu::Buffer>,
+    index_buffer: Option<wgpu::Buffer>,
+    node_uniform_buffer: Option<wgpu::Buffer>,
+    deform_buffer: Option<wgpu::Buffer>,
+    compute_bind_group: Option<wgpu::BindGroup>,
+
+    /// The uniforms/deforms last written to `node_uniform_buffer`/
+    /// `deform_buffer`, so `upload_changed_node_data` can skip the
+    /// `queue.write_buffer` call on frames where nothing moved.
+    last_node_uniforms: Vec<NodeUniform>,
+    last_deform_offsets: Vec<[f32; 2]>,
+
+    /// Textures referenced by drawable nodes, indexed by texture id.
+    /// Populated lazily with a 1x1 placeholder until the model carries
+    /// real decoded pixel data for its `thumbnail_id`/node texture ids.
+    textures: Vec<wgpu::TextureView>,
+    /// One bind group per entry in `drawables`, rebuilt whenever the
+    /// drawable list or the active sampler changes.
+    texture_bind_groups: Vec<wgpu::BindGroup>,
+    /// Set whenever `drawables` is rebuilt, so `rebuild_texture_bind_groups`
+    /// knows to rebuild even if the sampler choice hasn't changed.
+    texture_bind_groups_stale: bool,
+    /// The `preserve_pixels` value `texture_bind_groups` was last built
+    /// for.
+    texture_bind_groups_preserve_pixels: Option<bool>,
+
+    drawables: Vec<DrawableGpu>,
+}
+
+impl Renderer {
+    /// Creates a renderer that draws into textures of `target_format`.
+    pub fn new(device: wgpu::Device, queue: wgpu::Queue, target_format: wgpu::TextureFormat) -> Self {
+        let compute_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("inox2d-transform-compute-bgl"),
+            entries: &[
+                storage_entry(0, wgpu::ShaderStages::COMPUTE, true),
+                storage_entry(1, wgpu::ShaderStages::COMPUTE, true),
+                storage_entry(2, wgpu::ShaderStages::COMPUTE, true),
+                storage_entry(3, wgpu::ShaderStages::COMPUTE, false),
+            ],
+        });
+
+        let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("inox2d-transform-compute"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("transform.wgsl").into()),
+        });
+        let compute_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("inox2d-transform-compute-layout"),
+            bind_group_layouts: &[&compute_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("inox2d-transform-compute-pipeline"),
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_shader,
+            entry_point: "apply_transforms",
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let raster_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("inox2d-raster-bgl"),
+            entries: &[
+                storage_entry(0, wgpu::ShaderStages::VERTEX, true),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let raster_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("inox2d-raster"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("raster.wgsl").into()),
+        });
+        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("inox2d-raster-layout"),
+            bind_group_layouts: &[&raster_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("inox2d-raster-pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &raster_shader,
+                entry_point: "vs_main",
+                // Vertices are pulled from the storage buffer bound at
+                // group 0 binding 0 rather than a traditional vertex
+                // buffer, so no vertex buffer layout is declared here.
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &raster_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let nearest_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("inox2d-nearest-sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let linear_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("inox2d-linear-sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            device,
+            queue,
+            target_format,
+            compute_bind_group_layout,
+            raster_bind_group_layout,
+            compute_pipeline,
+            render_pipeline,
+            nearest_sampler,
+            linear_sampler,
+            vertex_buffer: None,
+            transformed_buffer: None,
+            index_buffer: None,
+            node_uniform_buffer: None,
+            deform_buffer: None,
+            compute_bind_group: None,
+            last_node_uniforms: Vec::new(),
+            last_deform_offsets: Vec::new(),
+            textures: Vec::new(),
+            texture_bind_groups: Vec::new(),
+            texture_bind_groups_stale: true,
+            texture_bind_groups_preserve_pixels: None,
+            drawables: Vec::new(),
+        }
+    }
+
+    /// Draws `puppet` into `target`, collecting its drawable nodes in
+    /// `ZSort` order, uploading only the transform/deform data that
+    /// changed since the last frame, and rasterizing with the sampler
+    /// chosen from `PuppetMeta::preserve_pixels`.
+    pub fn render(&mut self, puppet: &Puppet, target: &wgpu::TextureView) {
+        self.rebuild_drawables_if_stale(puppet);
+        self.upload_changed_node_data(puppet);
+        self.rebuild_texture_bind_groups(puppet.meta.preserve_pixels);
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("inox2d-frame-encoder"),
+            });
+
+        if let Some(bind_group) = &self.compute_bind_group {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("inox2d-transform-pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(&self.compute_pipeline);
+            compute_pass.set_bind_group(0, bind_group, &[]);
+            let workgroups = self.drawables.iter().map(|d| d.vertex_count).sum::<u32>().div_ceil(64);
+            compute_pass.dispatch_workgroups(workgroups.max(1), 1, 1);
+        }
+
+        {
+            let mut raster_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("inox2d-raster-pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            raster_pass.set_pipeline(&self.render_pipeline);
+
+            if let Some(index_buffer) = &self.index_buffer {
+                raster_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+
+                for (i, drawable) in self.drawables.iter().enumerate() {
+                    if let Some(bind_group) = self.texture_bind_groups.get(i) {
+                        raster_pass.set_bind_group(0, bind_group, &[]);
+                        raster_pass.draw_indexed(
+                            drawable.index_offset..drawable.index_offset + drawable.index_count,
+                            0,
+                            0..1,
+                        );
+                    }
+                }
+            }
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Re-sorts and re-uploads the drawable list (mesh topology,
+    /// textures) when the puppet's node set or its `ZSort` order has
+    /// changed.
+    fn rebuild_drawables_if_stale(&mut self, puppet: &Puppet) {
+        let mut drawables: Vec<_> = puppet.nodes.drawables().collect();
+        drawables.sort_by(|a, b| a.zsort_offset.total_cmp(&b.zsort_offset));
+
+        let unchanged = self.vertex_buffer.is_some()
+            && drawables.len() == self.drawables.len()
+            && drawables.iter().zip(&self.drawables).all(|(node, gpu)| node.uuid == gpu.node);
+        if unchanged {
+            return;
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut gpu_drawables = Vec::with_capacity(drawables.len());
+
+        for drawable in &drawables {
+            let vertex_offset = vertices.len() as u32;
+            let index_offset = indices.len() as u32;
+
+            vertices.extend(drawable.vertices.iter().zip(&drawable.uvs).map(|(p, uv)| Vertex {
+                position: [p.x, p.y],
+                uv: [uv.x, uv.y],
+            }));
+            indices.extend(drawable.indices.iter().map(|i| i + vertex_offset));
+
+            gpu_drawables.push(DrawableGpu {
+                node: drawable.uuid,
+                vertex_offset,
+                vertex_count: drawable.vertices.len() as u32,
+                index_offset,
+                index_count: drawable.indices.len() as u32,
+                texture_index: drawable.texture_id.unwrap_or(0),
+            });
+        }
+
+        let vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("inox2d-vertex-buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+        let transformed_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("inox2d-transformed-buffer"),
+            size: (vertices.len() * std::mem::size_of::<Vertex>()).max(1) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+        let index_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("inox2d-index-buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        // Sized for every drawable/vertex up front and reused for the
+        // life of this topology; `upload_changed_node_data` only ever
+        // `queue.write_buffer`s into these, it never recreates them.
+        let node_uniform_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("inox2d-node-uniform-buffer"),
+            size: (gpu_drawables.len() * std::mem::size_of::<NodeUniform>()).max(1) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let deform_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("inox2d-deform-buffer"),
+            size: (vertices.len() * std::mem::size_of::<[f32; 2]>()).max(1) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        self.compute_bind_group = Some(self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("inox2d-transform-bind-group"),
+            layout: &self.compute_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: deform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: node_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: transformed_buffer.as_entire_binding(),
+                },
+            ],
+        }));
+
+        self.vertex_buffer = Some(vertex_buffer);
+        self.transformed_buffer = Some(transformed_buffer);
+        self.index_buffer = Some(index_buffer);
+        self.node_uniform_buffer = Some(node_uniform_buffer);
+        self.deform_buffer = Some(deform_buffer);
+        self.drawables = gpu_drawables;
+
+        // Buffers were just recreated, so the cached snapshots no
+        // longer match what's on the GPU; drop them so the next
+        // `upload_changed_node_data` call writes a fresh copy, and
+        // force the texture bind groups (which reference
+        // `transformed_buffer`) to rebuild too.
+        self.last_node_uniforms.clear();
+        self.last_deform_offsets.clear();
+        self.texture_bind_groups_stale = true;
+    }
+
+    /// Writes per-node transform/deform data into the persistent
+    /// `node_uniform_buffer`/`deform_buffer`, skipping the upload
+    /// entirely when nothing has changed since the last call.
+    fn upload_changed_node_data(&mut self, puppet: &Puppet) {
+        let mut uniforms = Vec::with_capacity(self.drawables.len());
+        let mut deforms = Vec::new();
+
+        for drawable in &self.drawables {
+            let Some(node) = puppet.nodes.get(drawable.node) else {
+                continue;
+            };
+
+            uniforms.push(NodeUniform {
+                translation: [node.offset_tx, node.offset_ty],
+                scale: [node.offset_sx, node.offset_sy],
+                rotation: [node.offset_rx, node.offset_ry, node.offset_rz],
+                vertex_offset: drawable.vertex_offset,
+                vertex_count: drawable.vertex_count,
+                texture_index: drawable.texture_index,
+                _pad: [0, 0],
+            });
+            deforms.extend(node.deform_offset.iter().map(|v| [v.x, v.y]));
+        }
+
+        if uniforms != self.last_node_uniforms {
+            if let Some(buffer) = &self.node_uniform_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(&uniforms));
+            }
+            self.last_node_uniforms = uniforms;
+        }
+
+        if deforms != self.last_deform_offsets {
+            if let Some(buffer) = &self.deform_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(&deforms));
+            }
+            self.last_deform_offsets = deforms;
+        }
+    }
+
+    /// Ensures a placeholder texture exists for `index`, growing
+    /// `textures` as needed.
+    fn ensure_texture(&mut self, index: u32) {
+        while self.textures.len() <= index as usize {
+            let texture = self.create_placeholder_texture();
+            self.textures.push(texture);
+        }
+    }
+
+    /// A 1x1 opaque white texture, used until the model carries real
+    /// decoded pixel data for a referenced texture id.
+    fn create_placeholder_texture(&self) -> wgpu::TextureView {
+        let size = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("inox2d-placeholder-texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &[255, 255, 255, 255],
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4),
+                rows_per_image: Some(1),
+            },
+            size,
+        );
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Rebuilds the per-drawable raster bind groups against the current
+    /// `transformed_buffer`, each drawable's texture, and the sampler
+    /// chosen by `preserve_pixels`, skipping the rebuild when neither
+    /// the drawable list nor the sampler choice changed since last call.
+    fn rebuild_texture_bind_groups(&mut self, preserve_pixels: bool) {
+        if !self.texture_bind_groups_stale
+            && self.texture_bind_groups_preserve_pixels == Some(preserve_pixels)
+        {
+            return;
+        }
+
+        let texture_indices: Vec<u32> = self.drawables.iter().map(|d| d.texture_index).collect();
+        for &index in &texture_indices {
+            self.ensure_texture(index);
+        }
+
+        let Some(transformed_buffer) = self.transformed_buffer.as_ref() else {
+            self.texture_bind_groups.clear();
+            return;
+        };
+
+        let device = &self.device;
+        let layout = &self.raster_bind_group_layout;
+        let textures = &self.textures;
+        let sampler = if preserve_pixels {
+            &self.nearest_sampler
+        } else {
+            &self.linear_sampler
+        };
+
+        self.texture_bind_groups = texture_indices
+            .into_iter()
+            .map(|index| {
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("inox2d-raster-bind-group"),
+                    layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: transformed_buffer.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&textures[index as usize]),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::Sampler(sampler),
+                        },
+                    ],
+                })
+            })
+            .collect();
+
+        self.texture_bind_groups_stale = false;
+        self.texture_bind_groups_preserve_pixels = Some(preserve_pixels);
+    }
+}